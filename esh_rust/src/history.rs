@@ -0,0 +1,145 @@
+//! Optional ring-buffer command history.
+//!
+//! History is compiled in unconditionally but costs no buffer space when
+//! `HIST` is zero: the backing array is zero-sized and every operation becomes
+//! a no-op, so there is no separate feature flag for disabling it.
+
+/// A ring buffer of NUL-separated previous command lines.
+pub struct History<const HIST: usize> {
+    buf: [u8; HIST],
+    /// Index one past the most recently written byte.
+    head: usize,
+    /// Number of valid bytes currently stored, capped at `HIST`.
+    filled: usize,
+}
+
+impl<const HIST: usize> History<HIST> {
+    pub const fn new() -> Self {
+        History { buf: [0; HIST], head: 0, filled: 0 }
+    }
+
+    /// Record `line` as the most recent history entry. Does nothing if
+    /// history is disabled (`HIST == 0`), `line` is empty, or `line`
+    /// cannot fit alongside its terminator.
+    pub fn push(&mut self, line: &[u8]) {
+        let needed = line.len() + 1;
+        if HIST == 0 || line.is_empty() || needed > HIST {
+            return;
+        }
+        // Evict whole entries, oldest first, until the new one fits, so
+        // no partly overwritten entry is left behind to be recalled.
+        while self.filled + needed > HIST {
+            let mut tail = (self.head + HIST - self.filled) % HIST;
+            loop {
+                self.filled -= 1;
+                if self.buf[tail] == 0 {
+                    break;
+                }
+                tail = (tail + 1) % HIST;
+            }
+        }
+        for &b in line.iter().chain(core::iter::once(&0u8)) {
+            self.buf[self.head] = b;
+            self.head = (self.head + 1) % HIST;
+        }
+        self.filled += needed;
+    }
+
+    /// Copy the `n`th-from-most-recent history entry into `out`,
+    /// returning its length. `n == 0` is the most recently pushed entry.
+    /// Returns `None` if there is no such entry, or it does not fit in
+    /// `out`.
+    pub fn recall(&self, n: usize, out: &mut [u8]) -> Option<usize> {
+        if HIST == 0 {
+            return None;
+        }
+
+        let mut idx = self.head;
+        let mut scanned = 0usize;
+        let mut terminators = 0usize;
+
+        while scanned < self.filled {
+            idx = (idx + HIST - 1) % HIST;
+            scanned += 1;
+            if self.buf[idx] != 0 {
+                continue;
+            }
+            if terminators != n {
+                terminators += 1;
+                continue;
+            }
+
+            // `idx` is the NUL that terminates entry `n`; copy backward
+            // until the previous NUL (or the start of valid history).
+            let mut pos = idx;
+            let mut written = 0usize;
+            loop {
+                if scanned >= self.filled {
+                    break;
+                }
+                pos = (pos + HIST - 1) % HIST;
+                scanned += 1;
+                if self.buf[pos] == 0 {
+                    break;
+                }
+                if written >= out.len() {
+                    return None;
+                }
+                out[written] = self.buf[pos];
+                written += 1;
+            }
+            out[..written].reverse();
+            return Some(written);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_then_recall_round_trip() {
+        let mut hist: History<64> = History::new();
+        hist.push(b"first");
+        hist.push(b"second");
+
+        let mut out = [0u8; 16];
+        let len = hist.recall(0, &mut out).unwrap();
+        assert_eq!(&out[..len], b"second");
+
+        let len = hist.recall(1, &mut out).unwrap();
+        assert_eq!(&out[..len], b"first");
+    }
+
+    #[test]
+    fn recall_past_the_end_returns_none() {
+        let mut hist: History<64> = History::new();
+        hist.push(b"only");
+
+        let mut out = [0u8; 16];
+        assert!(hist.recall(1, &mut out).is_none());
+    }
+
+    #[test]
+    fn eviction_once_push_wraps_past_capacity() {
+        // Capacity for exactly two 3-byte entries ("ab\0" each).
+        let mut hist: History<6> = History::new();
+        hist.push(b"ab");
+        hist.push(b"cd");
+        // A third push must evict the oldest ("ab") to make room.
+        hist.push(b"ef");
+
+        let mut out = [0u8; 16];
+        let len = hist.recall(0, &mut out).unwrap();
+        assert_eq!(&out[..len], b"ef");
+
+        let len = hist.recall(1, &mut out).unwrap();
+        assert_eq!(&out[..len], b"cd");
+
+        // "ab" has been evicted and is no longer reachable.
+        assert!(hist.recall(2, &mut out).is_none());
+    }
+}
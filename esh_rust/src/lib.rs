@@ -1,4 +1,30 @@
-/* esh - embedded shell
+#![no_std]
+// The module doc below is a plain-text table of contents in the style of
+// the original esh documentation, not a markdown list; silence the lint
+// that wants ordinal lines like "2.1." indented as continuations.
+#![allow(clippy::doc_lazy_continuation)]
+
+/*!
+ * esh - embedded shell
+ * ====================
+ *
+ * *****************************************************************************
+ * * PLEASE read ALL of this documentation (all doc comments in this crate).   *
+ * * esh is simple, but a number of things need to be addressed by every      *
+ * * esh user.                                                                 *
+ * *****************************************************************************
+ *
+ * esh is a lightweight command shell for embedded applications, small
+ * enough to be used for (and intended for) debug UART consoles on
+ * microcontrollers. Features include line editing, automatic argument
+ * tokenizing (including sh-like quoting), and an optional history ring
+ * buffer.
+ *
+ * esh is pure, safe, `#![no_std]` Rust. There is no companion C library
+ * and no `esh_config.h` to provide out-of-band: every tunable is a const
+ * generic on `Esh` itself, so the same crate builds for any target with a
+ * Rust compiler, including `wasm32-unknown-unknown`, without a C
+ * toolchain.
  *
  * Copyright (c) 2016, Chris Pavlina.
  *
@@ -9,8 +35,8 @@
  * copies of the Software, and to permit persons to whom the Software is
  * furnished to do so, subject to the following conditions:
  *
- * The above copyright notice and this permission notice shall be included in all
- * copies or substantial portions of the Software.
+ * The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
  *
  * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
  * EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
@@ -19,107 +45,209 @@
  * DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR
  * OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE
  * OR OTHER DEALINGS IN THE SOFTWARE.
+ *
+ * -----------------------------------------------------------------------------
+ *
+ * 1.   Usage
+ * 2.   Configuring esh
+ * 2.1.     Line endings
+ * 2.2.     History (optional)
+ * 3.   Code documentation
+ * 3.1.     Basic interface: initialization and input
+ * 3.2.     Callback registration functions
+ * 4.   Private functions
+ *
+ * -----------------------------------------------------------------------------
+ *
+ * 1. Usage
+ * ========
+ *
+ * Pick buffer sizes and construct an instance:
+ *
+ * ```ignore
+ * // 80-byte line buffer, up to 8 arguments, 256 bytes of history.
+ * let mut esh: Esh<80, 8, 256> = Esh::new("% ");
+ * ```
+ *
+ * Register your callbacks with:
+ *
+ * ```ignore
+ * esh.register_command(command_callback);
+ * esh.register_print(print_callback);
+ *
+ * // Optional, see the documentation for this function:
+ * esh.register_overflow(overflow_callback);
+ * ```
+ *
+ * Print the initial prompt, then begin receiving characters from your
+ * serial interface and feeding them in:
+ *
+ * ```ignore
+ * esh.start();
+ * esh.rx(c);
+ * ```
+ *
+ * 2. Configuring esh
+ * ==================
+ *
+ * All configuration is via the three const generic parameters on `Esh`:
+ *
+ * ```ignore
+ * Esh<const BUF: usize, const ARGC: usize, const HIST: usize>
+ * ```
+ *
+ * * `BUF` - maximum length of a command line, in bytes.
+ * * `ARGC` - maximum argument count, including the command name.
+ * * `HIST` - size in bytes of the history ring buffer. Use `0` to disable
+ *   history entirely; the backing storage then costs nothing.
+ *
+ * 2.1. Line endings
+ * -----------------
+ *
+ * Internally, esh uses strictly `\n` line endings. A great many IO sources use
+ * different line endings; the user is responsible for translating them for esh.
+ * In general, most raw-mode unix-like terminals will give `\r` from the
+ * keyboard and require `\r\n` as output, so your input functions should
+ * translate `\r` to `\n`.
+ *
+ * 2.2. History (optional)
+ * -----------------------
+ *
+ * History is enabled by choosing a nonzero `HIST`. It is a simple ring
+ * buffer of NUL-separated lines, navigated with the up/down arrow keys;
+ * once it wraps, the oldest entries are silently evicted to make room for
+ * new ones.
  */
+// Tests need an allocator-backed container to capture callback output;
+// borrow std for them only, the production build stays pure no_std.
+#[cfg(test)]
+extern crate std;
 
-use std::ptr;
-use std::mem;
-use std::slice;
-use std::ops::Index;
-
-/// The main esh object. This is an opaque object representing an esh instance,
-/// and having methods for interacting with it.
-pub enum Esh {}
-enum Void {}
-
-/// Argument accessor. Provides a bound-checked interface to argc/argv without
-/// requiring any allocation.
-pub struct EshArgArray {
-    argc: i32,
-    argv: *mut *mut u8,
-}
+mod history;
+mod token;
+
+use core::ops::Index;
 
-extern "C" {
-    fn esh_init() -> *mut Esh;
-    fn esh_register_command(
-        esh: *mut Esh,
-        cb: extern fn(esh: *mut Esh, argc: i32, argv: *mut *mut u8, arg: *mut Void),
-        arg: *mut Void);
-    fn esh_register_print(
-        esh: *mut Esh,
-        cb: extern "C" fn(esh: *mut Esh, s: *const u8, arg: *mut Void),
-        arg: *mut Void);
-    fn esh_register_overflow(
-        esh: *mut Esh,
-        cb: extern "C" fn(*mut Esh, *const u8, *mut Void),
-        arg: *mut Void);
-    fn esh_rx(esh: *mut Esh, c: u8);
+use history::History;
+use token::{tokenize, TokenizeError};
+
+/// The main esh object.
+///
+/// `BUF` is the line buffer size in bytes, `ARGC` the maximum number of
+/// arguments (including the command name), and `HIST` the size in bytes
+/// of the history ring buffer (`0` to disable history).
+pub struct Esh<const BUF: usize, const ARGC: usize, const HIST: usize> {
+    buffer: [u8; BUF],
+    len: usize,
+    cursor: usize,
+    overflow: bool,
+    escape: Escape,
+    prompt: &'static str,
+    history: History<HIST>,
+    hist_index: Option<usize>,
+    print_cb: Option<fn(esh: &Self, s: &str)>,
+    command_cb: Option<fn(esh: &Self, args: &Args)>,
+    overflow_cb: Option<fn(esh: &Self, s: &[u8])>,
 }
 
-fn strlen(s: *const u8) -> usize {
-    let mut i: usize = 0;
-    loop {
-        let c = unsafe{*s.offset(i as isize)};
-        if c == 0 {
-            break;
-        } else {
-            i += 1;
-        }
-    }
-    return i;
+/// Parser state for ANSI escape sequences (arrow keys).
+enum Escape {
+    /// Not currently in an escape sequence.
+    None,
+    /// Received `ESC`, awaiting `[`.
+    Esc,
+    /// Received `ESC [`, awaiting the final byte.
+    Csi,
 }
 
-impl Esh {
-    /// Return an initialized esh object. Must be called before any other
-    /// functions.
-    ///
-    /// See `ESH_ALLOC` in `esh_config.h` - this should be `STATIC` or
-    /// `MALLOC`. If `STATIC`, `ESH_INSTANCES` must be defined to the
-    /// maximum number of instances, and references to these instances
-    /// will be returned.
-    ///
-    /// Return value:
-    ///
-    /// * `Some(&'static mut Esh)` - successful initialization
-    /// * `None` - static instance count was exceeded or malloc failed.
-    pub fn init() -> Option<&'static mut Esh> {
-        let esh = unsafe{esh_init()};
-        if esh == ptr::null_mut() {
-            return None;
-        } else {
-            return Some(unsafe{&mut *esh});
+/*
+ * -----------------------------------------------------------------------------
+ *
+ * 3. Code documentation
+ */
+impl<const BUF: usize, const ARGC: usize, const HIST: usize> Esh<BUF, ARGC, HIST> {
+    /*
+     * -------------------------------------------------------------------------
+     * 3.1. Basic interface: initialization and input
+     */
+
+    /// Return a new esh instance with the given prompt. Unlike older,
+    /// C-backed versions of esh, this can never fail: there is no
+    /// allocator and no static instance limit to exceed.
+    pub const fn new(prompt: &'static str) -> Self {
+        Esh {
+            buffer: [0; BUF],
+            len: 0,
+            cursor: 0,
+            overflow: false,
+            escape: Escape::None,
+            prompt,
+            history: History::new(),
+            hist_index: None,
+            print_cb: None,
+            command_cb: None,
+            overflow_cb: None,
         }
     }
 
-    extern "C" fn print_callback_wrapper(esh: *mut Esh, s: *const u8, arg: *mut Void) {
-        let func: fn(&Esh, &[u8]) = unsafe{mem::transmute(arg)};
+    /// Print the initial prompt. Call once, before the first `rx()`.
+    pub fn start(&mut self) {
+        self.redraw();
+    }
 
-        let i = strlen(s);
-        let string_slice = unsafe{slice::from_raw_parts(s, i)};
-        let esh_self = unsafe{&*esh};
+    /**
+     * Pass in a character that was received.
+     *
+     * This takes u8 instead of char because most inputs are byte-oriented.
+     * Note that esh does not currently have Unicode support; to properly play
+     * along with Rust (where &str is always UTF-8), only bytes in the
+     * intersection of ASCII and UTF-8 will be accepted; others will be silently
+     * dropped.
+     */
+    pub fn rx(&mut self, c: u8) {
+        match self.escape {
+            Escape::None => self.rx_plain(c),
+            Escape::Esc => {
+                self.escape = if c == b'[' { Escape::Csi } else { Escape::None };
+            }
+            Escape::Csi => {
+                match c {
+                    b'A' => self.history_prev(),
+                    b'B' => self.history_next(),
+                    b'C' => self.move_cursor_right(),
+                    b'D' => self.move_cursor_left(),
+                    _ => {}
+                }
+                self.escape = Escape::None;
+            }
+        }
+    }
 
-        func(esh_self, string_slice);
+    fn rx_plain(&mut self, c: u8) {
+        match c {
+            b'\n' => self.execute_line(),
+            0x08 | 0x7f => self.backspace(),
+            0x15 => self.clear_line(),
+            0x17 => self.delete_word_backward(),
+            0x1b => self.escape = Escape::Esc,
+            0x20..=0x7e => self.insert_char(c),
+            _ => {}
+        }
     }
 
+    /*
+     * -------------------------------------------------------------------------
+     * 3.2. Callback registration functions
+     */
+
     /// Register a callback to print a string.
     ///
     /// Callback arguments:
     ///
     /// * `esh` - the originating esh instance, allowing identification
-    /// * `s` - the string to print, as a slice of bytes
-    pub fn register_print(&mut self, cb: fn(esh: &Esh, s: &[u8])) {
-        let fp = cb as *mut Void;
-        unsafe {
-            esh_register_print(self, Esh::print_callback_wrapper, fp);
-        }
-    }
-
-    extern "C" fn command_callback_wrapper(
-            esh: *mut Esh, argc: i32, argv: *mut *mut u8, arg: *mut Void) {
-
-        let func: fn(&Esh, &EshArgArray) = unsafe{mem::transmute(arg)};
-        let argarray = EshArgArray{argc: argc, argv: argv};
-        let esh_self = unsafe{&*esh};
-        func(esh_self, &argarray);
+    /// * `s` - the string to print
+    pub fn register_print(&mut self, cb: fn(esh: &Self, s: &str)) {
+        self.print_cb = Some(cb);
     }
 
     /// Register a callback to execute a command.
@@ -128,22 +256,8 @@ impl Esh {
     ///
     /// * `esh` - the originating esh instance, allowing identification
     /// * `args` - a reference to an argument accessor object
-    pub fn register_command(&mut self, cb: fn(esh: &Esh, args: &EshArgArray)) {
-        let fp = cb as *mut Void;
-        unsafe {
-            esh_register_command(self, Esh::command_callback_wrapper, fp);
-        }
-    }
-
-    extern "C" fn overflow_callback_wrapper(
-            esh: *mut Esh, buf: *const u8, arg: *mut Void) {
-
-        let func: fn(&Esh, &[u8]) = unsafe{mem::transmute(arg)};
-        let i = strlen(buf);
-        let buf_slice = unsafe{slice::from_raw_parts(buf, i)};
-        let esh_self = unsafe{&*esh};
-
-        func(esh_self, buf_slice);
+    pub fn register_command(&mut self, cb: fn(esh: &Self, args: &Args)) {
+        self.command_cb = Some(cb);
     }
 
     /// Register a callback to notify about overflow. Optional; esh has an
@@ -152,44 +266,377 @@ impl Esh {
     /// Callback arguments:
     ///
     /// * `esh` - the originating esh instance, allowing identification
-    /// * `s` - the contents of the buffer, excluding overflow
-    pub fn register_overflow(&mut self, cb: fn(esh: &Esh, s: &[u8])) {
-        let fp = cb as *mut Void;
-        unsafe {
-            esh_register_overflow(self, Esh::overflow_callback_wrapper, fp);
+    /// * `s` - the contents of the buffer, excluding the character that
+    ///   overflowed it
+    pub fn register_overflow(&mut self, cb: fn(esh: &Self, s: &[u8])) {
+        self.overflow_cb = Some(cb);
+    }
+
+    // -------------------------------------------------------------------
+    // 4. Private functions
+    // -------------------------------------------------------------------
+
+    fn execute_line(&mut self) {
+        if !self.overflow {
+            self.history.push(&self.buffer[..self.len]);
+
+            let mut ranges = [(0usize, 0usize); ARGC];
+            match tokenize(&mut self.buffer[..self.len], &mut ranges) {
+                Ok(0) => {}
+                Ok(count) => {
+                    let args = Args { bytes: &self.buffer[..self.len], ranges: &ranges[..count] };
+                    if let Some(cb) = self.command_cb {
+                        cb(self, &args);
+                    }
+                }
+                Err(TokenizeError::UnmatchedQuote) => self.print("\r\nerror: unmatched quote\r\n"),
+                Err(TokenizeError::TooManyTokens) => self.print("\r\nerror: too many arguments\r\n"),
+            }
         }
+
+        self.overflow = false;
+        self.len = 0;
+        self.cursor = 0;
+        self.hist_index = None;
+        self.print("\r\n");
+        self.redraw();
     }
 
-    /// Pass in a character that was received.
-    pub fn rx(&mut self, c: u8) {
-        unsafe {
-            esh_rx(self, c);
+    fn insert_char(&mut self, c: u8) {
+        if self.overflow {
+            return;
+        }
+        if self.len >= BUF {
+            self.overflow = true;
+            self.emit_overflow();
+            return;
+        }
+        self.buffer.copy_within(self.cursor..self.len, self.cursor + 1);
+        self.buffer[self.cursor] = c;
+        self.len += 1;
+        self.cursor += 1;
+        self.hist_index = None;
+        self.redraw();
+    }
+
+    fn backspace(&mut self) {
+        if self.cursor == 0 {
+            return;
+        }
+        self.buffer.copy_within(self.cursor..self.len, self.cursor - 1);
+        self.len -= 1;
+        self.cursor -= 1;
+        self.overflow = false;
+        self.hist_index = None;
+        self.redraw();
+    }
+
+    fn clear_line(&mut self) {
+        self.len = 0;
+        self.cursor = 0;
+        self.overflow = false;
+        self.hist_index = None;
+        self.redraw();
+    }
+
+    fn delete_word_backward(&mut self) {
+        if self.cursor == 0 {
+            return;
+        }
+        let mut start = self.cursor;
+        while start > 0 && self.buffer[start - 1] == b' ' {
+            start -= 1;
+        }
+        while start > 0 && self.buffer[start - 1] != b' ' {
+            start -= 1;
+        }
+        self.buffer.copy_within(self.cursor..self.len, start);
+        self.len -= self.cursor - start;
+        self.cursor = start;
+        self.overflow = false;
+        self.hist_index = None;
+        self.redraw();
+    }
+
+    fn move_cursor_left(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.redraw();
+        }
+    }
+
+    fn move_cursor_right(&mut self) {
+        if self.cursor < self.len {
+            self.cursor += 1;
+            self.redraw();
+        }
+    }
+
+    fn history_prev(&mut self) {
+        let next = self.hist_index.map_or(0, |n| n + 1);
+        if let Some(len) = self.history.recall(next, &mut self.buffer) {
+            self.hist_index = Some(next);
+            self.len = len;
+            self.cursor = len;
+            self.redraw();
+        }
+    }
+
+    fn history_next(&mut self) {
+        match self.hist_index {
+            None => {}
+            Some(0) => {
+                self.hist_index = None;
+                self.len = 0;
+                self.cursor = 0;
+                self.redraw();
+            }
+            Some(n) => {
+                if let Some(len) = self.history.recall(n - 1, &mut self.buffer) {
+                    self.hist_index = Some(n - 1);
+                    self.len = len;
+                    self.cursor = len;
+                    self.redraw();
+                }
+            }
+        }
+    }
+
+    fn emit_overflow(&mut self) {
+        if let Some(cb) = self.overflow_cb {
+            cb(self, &self.buffer[..self.len]);
+        } else {
+            self.print("\r\nerror: line too long\r\n");
+        }
+    }
+
+    /// Redraw the whole line from the prompt onward, leaving the cursor
+    /// positioned at `self.cursor`. Simpler (if slightly more verbose on
+    /// the wire) than tracking incremental terminal diffs, and just as
+    /// correct after any edit.
+    fn redraw(&mut self) {
+        self.print("\r");
+        self.print(self.prompt);
+        // Safe: every byte in `buffer[..len]` came from `insert_char`,
+        // which only accepts printable ASCII.
+        let line = unsafe { core::str::from_utf8_unchecked(&self.buffer[..self.len]) };
+        self.print(line);
+        self.print("\x1b[K");
+
+        let back = self.len - self.cursor;
+        if back > 0 {
+            let mut seq = [0u8; CURSOR_LEFT_SEQ_LEN];
+            let n = write_cursor_left(&mut seq, back);
+            let s = unsafe { core::str::from_utf8_unchecked(&seq[..n]) };
+            self.print(s);
+        }
+    }
+
+    fn print(&self, s: &str) {
+        if let Some(cb) = self.print_cb {
+            cb(self, s);
         }
     }
 }
 
-impl EshArgArray {
+/// Size of the buffer passed to [`write_cursor_left`]: `"\x1b["` (2) plus
+/// every decimal digit `usize::MAX` can have, plus the trailing `'D'` (1).
+/// Sized from `usize` itself rather than a guessed constant so it can
+/// never overflow regardless of how large a caller's `BUF` is.
+const CURSOR_LEFT_SEQ_LEN: usize = 2 + usize::MAX.ilog10() as usize + 1 + 1;
+
+/// Write the ANSI "cursor left `n`" escape sequence into `buf`, returning
+/// how many bytes were written.
+fn write_cursor_left(buf: &mut [u8; CURSOR_LEFT_SEQ_LEN], n: usize) -> usize {
+    buf[0] = 0x1b;
+    buf[1] = b'[';
+    let mut i = 2;
+    if n == 0 {
+        buf[i] = b'0';
+        i += 1;
+    } else {
+        let start = i;
+        let mut v = n;
+        while v > 0 {
+            buf[i] = b'0' + (v % 10) as u8;
+            v /= 10;
+            i += 1;
+        }
+        buf[start..i].reverse();
+    }
+    buf[i] = b'D';
+    i + 1
+}
 
+/// Argument accessor. Provides a bound-checked interface to the tokenized
+/// arguments of a command line without requiring any allocation.
+pub struct Args<'a> {
+    bytes: &'a [u8],
+    ranges: &'a [(usize, usize)],
+}
+
+impl<'a> Args<'a> {
     /// Return the number of arguments, including the command name.
     pub fn len(&self) -> usize {
-        return self.argc as usize;
+        self.ranges.len()
+    }
+
+    /// Return `true` if there are no arguments at all.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
     }
 }
 
-impl Index<usize> for EshArgArray {
-    type Output = [u8];
+impl<'a> Index<usize> for Args<'a> {
+    type Output = str;
 
-    /// Return an argument. Indices start from zero, with args[0] being the
-    /// command name. If `index` is out of bounds, an empty argument is
-    /// returned.
-    fn index<'a> (&'a self, index: usize) -> &'a [u8] {
-        if index >= self.argc as usize {
-            return &[];
-        } else {
-            let arg = unsafe{*self.argv.offset(index as isize)};
-            let len = strlen(arg);
-            return unsafe{slice::from_raw_parts(arg, len)};
-        }
+    /// Return an argument. Indices start from zero, with `args[0]` being
+    /// the command name. If `index` is out of bounds, an empty argument
+    /// is returned.
+    fn index(&self, index: usize) -> &str {
+        let Some(&(start, end)) = self.ranges.get(index) else {
+            return "";
+        };
+        // Safe: ranges are produced by `tokenize` from bytes that were
+        // all printable ASCII to begin with.
+        unsafe { core::str::from_utf8_unchecked(&self.bytes[start..end]) }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::vec::Vec;
+
+    std::thread_local! {
+        static OUTPUT: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// A `print_cb` that appends everything it's given to `OUTPUT`, so
+    /// tests can drive `Esh::rx` through its public interface and inspect
+    /// what would have gone out over the wire.
+    fn capture<const BUF: usize, const ARGC: usize, const HIST: usize>(
+        _esh: &Esh<BUF, ARGC, HIST>,
+        s: &str,
+    ) {
+        OUTPUT.with(|o| o.borrow_mut().extend_from_slice(s.as_bytes()));
+    }
+
+    fn take_output() -> Vec<u8> {
+        OUTPUT.with(|o| o.replace(Vec::new()))
+    }
+
+    fn feed<const BUF: usize, const ARGC: usize, const HIST: usize>(
+        esh: &mut Esh<BUF, ARGC, HIST>,
+        s: &[u8],
+    ) {
+        for &c in s {
+            esh.rx(c);
+        }
+    }
+
+    /// Send an arrow key: `ESC [ <final>`.
+    fn arrow<const BUF: usize, const ARGC: usize, const HIST: usize>(
+        esh: &mut Esh<BUF, ARGC, HIST>,
+        key: u8,
+    ) {
+        esh.rx(0x1b);
+        esh.rx(b'[');
+        esh.rx(key);
+    }
+
+    #[test]
+    fn overflow_then_recover_via_backspace() {
+        let mut esh: Esh<5, 4, 0> = Esh::new("% ");
+        esh.register_print(capture::<5, 4, 0>);
+        take_output();
+
+        feed(&mut esh, b"abcde");
+        assert_eq!(esh.len, 5);
+        assert!(!esh.overflow);
+
+        // One more character overflows the full buffer.
+        esh.rx(b'f');
+        assert!(esh.overflow);
+        assert_eq!(esh.len, 5);
+
+        // While still overflowed, further input is dropped.
+        esh.rx(b'g');
+        assert_eq!(esh.len, 5);
+
+        // Backspacing back under BUF clears the stuck state...
+        esh.rx(0x08);
+        assert_eq!(esh.len, 4);
+        assert!(!esh.overflow);
+
+        // ...and input is accepted again.
+        esh.rx(b'z');
+        assert_eq!(esh.len, 5);
+        assert_eq!(&esh.buffer[..esh.len], b"abcdz");
+    }
+
+    #[test]
+    fn backspace_and_word_kill() {
+        let mut esh: Esh<32, 4, 0> = Esh::new("% ");
+        esh.register_print(capture::<32, 4, 0>);
+        take_output();
+
+        feed(&mut esh, b"foo bar");
+        esh.rx(0x7f); // backspace (DEL)
+        assert_eq!(&esh.buffer[..esh.len], b"foo ba");
+
+        esh.rx(0x17); // ctrl-W, delete word backward
+        assert_eq!(&esh.buffer[..esh.len], b"foo ");
+    }
+
+    #[test]
+    fn cursor_movement_and_mid_line_insert() {
+        let mut esh: Esh<16, 4, 0> = Esh::new("% ");
+        esh.register_print(capture::<16, 4, 0>);
+        take_output();
+
+        feed(&mut esh, b"ac");
+        arrow(&mut esh, b'D'); // left
+        esh.rx(b'b');
+
+        assert_eq!(&esh.buffer[..esh.len], b"abc");
+        assert_eq!(esh.cursor, 2);
+    }
+
+    #[test]
+    fn history_navigation_with_arrow_keys() {
+        let mut esh: Esh<16, 4, 64> = Esh::new("% ");
+        esh.register_print(capture::<16, 4, 64>);
+        take_output();
+
+        feed(&mut esh, b"first");
+        esh.rx(b'\n');
+        feed(&mut esh, b"second");
+        esh.rx(b'\n');
+
+        arrow(&mut esh, b'A'); // up: most recent entry
+        assert_eq!(&esh.buffer[..esh.len], b"second");
+
+        arrow(&mut esh, b'A'); // up again: one further back
+        assert_eq!(&esh.buffer[..esh.len], b"first");
+
+        arrow(&mut esh, b'B'); // down: back to the more recent entry
+        assert_eq!(&esh.buffer[..esh.len], b"second");
+
+        arrow(&mut esh, b'B'); // down past the start: back to an empty line
+        assert_eq!(esh.len, 0);
+    }
+
+    #[test]
+    fn overflow_without_callback_prints_default_message() {
+        let mut esh: Esh<2, 4, 0> = Esh::new("% ");
+        esh.register_print(capture::<2, 4, 0>);
+        feed(&mut esh, b"ab");
+        take_output();
+
+        esh.rx(b'c');
+        let out = take_output();
+        assert!(core::str::from_utf8(&out).unwrap().contains("line too long"));
+    }
+}
@@ -0,0 +1,166 @@
+//! Sh-style argument tokenizer.
+//!
+//! Splits a command line into whitespace-separated arguments, honoring
+//! single quotes, double quotes, and backslash escapes the way a POSIX
+//! shell does. Tokenization happens in place: quote and escape characters
+//! are removed from `line` itself, and each argument becomes a `(start,
+//! end)` byte range into what remains, so no extra storage is needed
+//! beyond the caller's `ranges` array.
+
+/// An error produced while tokenizing a command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenizeError {
+    /// The line has an unterminated `'` or `"` quote.
+    UnmatchedQuote,
+    /// More arguments were found than `ranges` can hold.
+    TooManyTokens,
+}
+
+/// Tokenize `line` in place, writing up to `ranges.len()` argument spans
+/// into `ranges` and returning how many were found.
+///
+/// Because quote and escape characters are stripped as part of
+/// tokenizing, the returned ranges index into the *rewritten* contents of
+/// `line`, not the original input.
+pub fn tokenize(
+    line: &mut [u8],
+    ranges: &mut [(usize, usize)],
+) -> Result<usize, TokenizeError> {
+    let len = line.len();
+    let mut read = 0;
+    let mut write = 0;
+    let mut count = 0;
+
+    while read < len {
+        while read < len && is_space(line[read]) {
+            read += 1;
+        }
+        if read >= len {
+            break;
+        }
+        if count >= ranges.len() {
+            return Err(TokenizeError::TooManyTokens);
+        }
+
+        let start = write;
+        let mut quote: Option<u8> = None;
+
+        loop {
+            if read >= len {
+                if quote.is_some() {
+                    return Err(TokenizeError::UnmatchedQuote);
+                }
+                break;
+            }
+
+            let c = line[read];
+            match quote {
+                Some(q) if c == q => {
+                    quote = None;
+                    read += 1;
+                }
+                Some(b'"') if c == b'\\' && read + 1 < len && is_escapable_in_dquote(line[read + 1]) => {
+                    line[write] = line[read + 1];
+                    write += 1;
+                    read += 2;
+                }
+                Some(_) => {
+                    line[write] = c;
+                    write += 1;
+                    read += 1;
+                }
+                None if c == b'\'' || c == b'"' => {
+                    quote = Some(c);
+                    read += 1;
+                }
+                None if c == b'\\' && read + 1 < len => {
+                    line[write] = line[read + 1];
+                    write += 1;
+                    read += 2;
+                }
+                None if is_space(c) => break,
+                None => {
+                    line[write] = c;
+                    write += 1;
+                    read += 1;
+                }
+            }
+        }
+
+        ranges[count] = (start, write);
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+fn is_space(c: u8) -> bool {
+    c == b' ' || c == b'\t'
+}
+
+fn is_escapable_in_dquote(c: u8) -> bool {
+    matches!(c, b'"' | b'\\' | b'$' | b'`')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arg(line: &[u8], range: (usize, usize)) -> &str {
+        core::str::from_utf8(&line[range.0..range.1]).unwrap()
+    }
+
+    #[test]
+    fn single_quotes() {
+        let mut line = *b"echo 'a b  c'";
+        let mut ranges = [(0usize, 0usize); 8];
+        let count = tokenize(&mut line, &mut ranges).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(arg(&line, ranges[0]), "echo");
+        assert_eq!(arg(&line, ranges[1]), "a b  c");
+    }
+
+    #[test]
+    fn double_quotes() {
+        let mut line = *b"echo \"a b\"";
+        let mut ranges = [(0usize, 0usize); 8];
+        let count = tokenize(&mut line, &mut ranges).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(arg(&line, ranges[1]), "a b");
+    }
+
+    #[test]
+    fn backslash_escapes_in_and_out_of_quotes() {
+        let mut line = *b"a\\ b \"c\\\"d\" 'e\\f'";
+        let mut ranges = [(0usize, 0usize); 8];
+        let count = tokenize(&mut line, &mut ranges).unwrap();
+        assert_eq!(count, 3);
+        // Outside quotes, backslash escapes the next byte.
+        assert_eq!(arg(&line, ranges[0]), "a b");
+        // Inside double quotes, only a defined set of bytes is escapable.
+        assert_eq!(arg(&line, ranges[1]), "c\"d");
+        // Inside single quotes, backslash is literal.
+        assert_eq!(arg(&line, ranges[2]), "e\\f");
+    }
+
+    #[test]
+    fn unmatched_quote_is_an_error() {
+        let mut line = *b"echo 'unterminated";
+        let mut ranges = [(0usize, 0usize); 8];
+        assert_eq!(tokenize(&mut line, &mut ranges), Err(TokenizeError::UnmatchedQuote));
+    }
+
+    #[test]
+    fn too_many_tokens_is_an_error() {
+        let mut line = *b"a b c d e f g h i";
+        let mut ranges = [(0usize, 0usize); 4];
+        assert_eq!(tokenize(&mut line, &mut ranges), Err(TokenizeError::TooManyTokens));
+    }
+
+    #[test]
+    fn empty_input_yields_no_tokens() {
+        let mut line = *b"   ";
+        let mut ranges = [(0usize, 0usize); 4];
+        assert_eq!(tokenize(&mut line, &mut ranges), Ok(0));
+    }
+}